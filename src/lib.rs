@@ -1,6 +1,7 @@
 use pyo3::prelude::*;
 use rayon::prelude::*;
-use geo::{Polygon, Coord, LineString};
+use geo::{Polygon, Coord, LineString, Area, BooleanOps};
+use geo::algorithm::bounding_rect::BoundingRect;
 use rstar::{RTree, AABB};
 use std::collections::HashMap;
 
@@ -12,7 +13,7 @@ struct Point {
 }
 
 /// Simple rectangle for fast collision detection
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 struct Rectangle {
     min_x: f64,
     min_y: f64,
@@ -42,6 +43,817 @@ impl Rectangle {
     }
 }
 
+/// Build a `geo::Polygon` from a Python-supplied vertex ring
+fn to_geo_polygon(ring: &[(f64, f64)]) -> Polygon<f64> {
+    let coords: Vec<Coord<f64>> = ring.iter().map(|(x, y)| Coord { x: *x, y: *y }).collect();
+    Polygon::new(LineString::new(coords), vec![])
+}
+
+/// Axis-aligned bounding box of a vertex ring, used as the cheap reject
+/// before the exact polygon test.
+fn bounds_of(ring: &[(f64, f64)]) -> Rectangle {
+    let rect = to_geo_polygon(ring)
+        .bounding_rect()
+        .expect("a carpet ring must have at least one vertex");
+    Rectangle::new(rect.min().x, rect.min().y, rect.max().x, rect.max().y)
+}
+
+/// Point-in-polygon test via ray casting
+fn point_in_polygon(point: (f64, f64), ring: &[(f64, f64)]) -> bool {
+    let mut inside = false;
+    let n = ring.len();
+    let mut j = n - 1;
+    for i in 0..n {
+        let (xi, yi) = ring[i];
+        let (xj, yj) = ring[j];
+        if ((yi > point.1) != (yj > point.1))
+            && (point.0 < (xj - xi) * (point.1 - yi) / (yj - yi) + xi)
+        {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+/// Exact overlap test between two polygon vertex rings, used once the cheap
+/// AABB reject fails to rule a pair out. Two polygons that only touch along
+/// a shared vertex or edge (zero-area contact) must not count as overlapping,
+/// or two carpets could never be nested flush against each other — so this
+/// checks the actual intersection area via `geo`'s boolean ops rather than a
+/// hand-rolled edge-crossing test, which can't tell a touch from a crossing.
+fn polygons_overlap(a: &[(f64, f64)], b: &[(f64, f64)]) -> bool {
+    to_geo_polygon(a).intersection(&to_geo_polygon(b)).unsigned_area() > 1e-9
+}
+
+/// Exact polygon collision check, with a cheap AABB reject before the exact test
+#[pyfunction]
+fn polygon_collision_check(
+    polygon_a: Vec<(f64, f64)>,
+    polygon_b: Vec<(f64, f64)>,
+) -> bool {
+    let rect_a = bounds_of(&polygon_a);
+    let rect_b = bounds_of(&polygon_b);
+
+    if !rect_a.intersects(&rect_b) {
+        return false;
+    }
+
+    polygons_overlap(&polygon_a, &polygon_b)
+}
+
+/// Grid search for collision-free positions using exact polygon geometry
+/// instead of AABB-only checks, for the irregular carpet shapes this crate
+/// nests.
+#[pyfunction]
+fn fast_grid_search_polygon(
+    carpet_ring: Vec<(f64, f64)>,  // carpet vertex ring at its reference position
+    placed_rings: Vec<Vec<(f64, f64)>>,  // vertex rings of already-placed polygons
+    sheet_width: f64,
+    sheet_height: f64,
+    grid_size: usize,
+) -> Option<(f64, f64)> {
+    let carpet_bounds = bounds_of(&carpet_ring);
+    let carpet_width = carpet_bounds.max_x - carpet_bounds.min_x;
+    let carpet_height = carpet_bounds.max_y - carpet_bounds.min_y;
+
+    let obstacle_bounds: Vec<Rectangle> = placed_rings.iter().map(|r| bounds_of(r)).collect();
+
+    let x_step = if grid_size > 1 {
+        (sheet_width - carpet_width) / (grid_size as f64 - 1.0)
+    } else {
+        0.0
+    };
+    let y_step = if grid_size > 1 {
+        (sheet_height - carpet_height) / (grid_size as f64 - 1.0)
+    } else {
+        0.0
+    };
+
+    let positions: Vec<(f64, f64)> = (0..grid_size)
+        .flat_map(|i| {
+            (0..grid_size).map(move |j| {
+                let x = if grid_size == 1 { 0.0 } else { i as f64 * x_step };
+                let y = if grid_size == 1 { 0.0 } else { j as f64 * y_step };
+                (x, y)
+            })
+        })
+        .collect();
+
+    positions
+        .par_iter()
+        .find_first(|(x, y)| {
+            let dx = x - carpet_bounds.min_x;
+            let dy = y - carpet_bounds.min_y;
+            let test_bounds = carpet_bounds.translate(dx, dy);
+
+            if test_bounds.min_x < 0.0 || test_bounds.min_y < 0.0 ||
+               test_bounds.max_x > sheet_width || test_bounds.max_y > sheet_height {
+                return false;
+            }
+
+            // Cheap AABB reject first, exact polygon test only for survivors
+            let candidates: Vec<usize> = obstacle_bounds
+                .iter()
+                .enumerate()
+                .filter(|(_, bounds)| test_bounds.intersects(bounds))
+                .map(|(idx, _)| idx)
+                .collect();
+
+            if candidates.is_empty() {
+                return true;
+            }
+
+            let test_ring: Vec<(f64, f64)> = carpet_ring
+                .iter()
+                .map(|(x, y)| (x + dx, y + dy))
+                .collect();
+
+            !candidates
+                .iter()
+                .any(|&idx| polygons_overlap(&test_ring, &placed_rings[idx]))
+        })
+        .map(|(x, y)| (*x, *y))
+}
+
+#[cfg(test)]
+mod polygon_collision_tests {
+    use super::*;
+
+    #[test]
+    fn polygon_collision_check_ignores_shared_edge() {
+        // Two right triangles sharing only their hypotenuse: AABBs overlap,
+        // but the actual intersection area is zero.
+        let a = vec![(0.0, 0.0), (4.0, 0.0), (0.0, 4.0)];
+        let b = vec![(4.0, 0.0), (4.0, 4.0), (0.0, 4.0)];
+
+        assert!(!polygon_collision_check(a, b));
+    }
+
+    #[test]
+    fn polygon_collision_check_detects_real_overlap() {
+        let a = vec![(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)];
+        let b = vec![(2.0, 2.0), (6.0, 2.0), (6.0, 6.0), (2.0, 6.0)];
+
+        assert!(polygon_collision_check(a, b));
+    }
+
+    #[test]
+    fn fast_grid_search_polygon_nests_flush_against_touching_triangle() {
+        // An obstacle triangle whose AABB spans the whole sheet; only a
+        // carpet placed flush against its hypotenuse (touching, not
+        // overlapping) can fit. A buggy closed-interval overlap test would
+        // reject every such position and return None.
+        let carpet_ring = vec![(0.0, 0.0), (4.0, 0.0), (0.0, 4.0)];
+        let obstacle = vec![(4.0, 0.0), (4.0, 4.0), (0.0, 4.0)];
+
+        let (x, y) = fast_grid_search_polygon(carpet_ring, vec![obstacle], 4.0, 4.0, 5)
+            .expect("the flush placement against the shared hypotenuse should be found");
+        assert_eq!((x, y), (0.0, 0.0));
+    }
+}
+
+/// Signed area of a vertex ring (positive when wound counter-clockwise)
+fn signed_area(ring: &[(f64, f64)]) -> f64 {
+    let n = ring.len();
+    let mut area = 0.0;
+    for i in 0..n {
+        let (x1, y1) = ring[i];
+        let (x2, y2) = ring[(i + 1) % n];
+        area += x1 * y2 - x2 * y1;
+    }
+    area / 2.0
+}
+
+/// Re-wind a ring counter-clockwise if it isn't already
+fn ensure_ccw(ring: &[(f64, f64)]) -> Vec<(f64, f64)> {
+    if signed_area(ring) < 0.0 {
+        ring.iter().rev().copied().collect()
+    } else {
+        ring.to_vec()
+    }
+}
+
+/// True when every turn of a CCW ring bends the same way, i.e. the polygon
+/// has no reflex vertices
+fn is_convex(ring: &[(f64, f64)]) -> bool {
+    let ring = ensure_ccw(ring);
+    let n = ring.len();
+    ring.iter().enumerate().all(|(i, &(x1, y1))| {
+        let (x0, y0) = ring[(i + n - 1) % n];
+        let (x2, y2) = ring[(i + 1) % n];
+        let cross = (x1 - x0) * (y2 - y1) - (y1 - y0) * (x2 - x1);
+        cross >= -1e-9
+    })
+}
+
+/// Reflect every vertex through the origin (i.e. negate the polygon, -A).
+/// Negating coordinates is a 180-degree rotation, which preserves winding
+/// order, so a CCW ring stays CCW with no re-reversal needed here;
+/// `minkowski_sum_convex`, which receives the result, calls `ensure_ccw` on
+/// both of its inputs regardless.
+fn reflect_through_origin(ring: &[(f64, f64)]) -> Vec<(f64, f64)> {
+    ring.iter().map(|(x, y)| (-x, -y)).collect()
+}
+
+/// Index of the bottom-most (then left-most) vertex, the canonical start
+/// point for merging edge vectors by polar angle
+fn bottom_left_index(ring: &[(f64, f64)]) -> usize {
+    ring.iter()
+        .enumerate()
+        .min_by(|(_, (ax, ay)), (_, (bx, by))| {
+            ay.partial_cmp(by).unwrap().then(ax.partial_cmp(bx).unwrap())
+        })
+        .map(|(i, _)| i)
+        .unwrap()
+}
+
+/// Minkowski sum of two convex CCW polygons, built by merging their edge
+/// vectors in increasing polar-angle order starting from each one's
+/// bottom-left vertex.
+fn minkowski_sum_convex(p: &[(f64, f64)], q: &[(f64, f64)]) -> Vec<(f64, f64)> {
+    let p = ensure_ccw(p);
+    let q = ensure_ccw(q);
+    let p_start = bottom_left_index(&p);
+    let q_start = bottom_left_index(&q);
+
+    let edges = |ring: &[(f64, f64)], start: usize| -> Vec<(f64, f64)> {
+        let n = ring.len();
+        (0..n)
+            .map(|i| {
+                let a = ring[(start + i) % n];
+                let b = ring[(start + i + 1) % n];
+                (b.0 - a.0, b.1 - a.1)
+            })
+            .collect()
+    };
+    let angle_of = |(x, y): (f64, f64)| {
+        let a = y.atan2(x);
+        if a < 0.0 { a + 2.0 * std::f64::consts::PI } else { a }
+    };
+
+    let p_edges = edges(&p, p_start);
+    let q_edges = edges(&q, q_start);
+
+    let mut result = Vec::with_capacity(p_edges.len() + q_edges.len());
+    let mut point = (p[p_start].0 + q[q_start].0, p[p_start].1 + q[q_start].1);
+    result.push(point);
+
+    let (mut pi, mut qi) = (0, 0);
+    while pi < p_edges.len() || qi < q_edges.len() {
+        let take_p = if pi >= p_edges.len() {
+            false
+        } else if qi >= q_edges.len() {
+            true
+        } else {
+            angle_of(p_edges[pi]) <= angle_of(q_edges[qi])
+        };
+
+        let edge = if take_p {
+            pi += 1;
+            p_edges[pi - 1]
+        } else {
+            qi += 1;
+            q_edges[qi - 1]
+        };
+
+        point = (point.0 + edge.0, point.1 + edge.1);
+        result.push(point);
+    }
+    result.pop(); // last point closes the ring back to the start
+    result
+}
+
+/// No-fit polygon of obstacle `b` relative to the moving carpet `a`: every
+/// point where `a`'s reference vertex can sit on `b`'s boundary without
+/// overlapping it. For convex shapes this is the Minkowski sum of `b` and
+/// the carpet reflected through the origin (-a). Concave carpets or
+/// obstacles are decomposed into convex pieces via `convex_pieces` (ear
+/// clipping); rather than unioning those pieces into a single ring (which
+/// `minkowski_sum_convex`'s edge-merge can't do), each
+/// pairwise sub-NFP is kept as its own ring so containment/candidate tests
+/// never join two unrelated pieces with a phantom edge.
+fn no_fit_polygon(carpet: &[(f64, f64)], obstacle: &[(f64, f64)]) -> Vec<Vec<(f64, f64)>> {
+    let carpet_pieces = convex_pieces(carpet);
+    let obstacle_pieces = convex_pieces(obstacle);
+
+    let mut nfps = Vec::new();
+    for b in &obstacle_pieces {
+        for a in &carpet_pieces {
+            nfps.push(minkowski_sum_convex(b, &reflect_through_origin(a)));
+        }
+    }
+    nfps
+}
+
+/// True when `point` falls inside any of an obstacle's (possibly several,
+/// un-unioned) sub-NFP rings, i.e. placing the carpet's reference vertex
+/// there would overlap that obstacle.
+fn inside_any_nfp(point: (f64, f64), nfps: &[Vec<(f64, f64)>]) -> bool {
+    nfps.iter().any(|ring| point_in_polygon(point, ring))
+}
+
+/// Decompose a polygon into convex pieces. Convex input is returned as-is;
+/// concave input falls back to ear-clipping triangulation, since every
+/// triangle is trivially convex. A centroid fan was tried first but isn't
+/// valid in general: the centroid of a concave ring isn't guaranteed to see
+/// every edge, so fan triangles can poke outside the polygon (or degenerate
+/// entirely when the centroid lands on a reflex vertex, as it does for a
+/// symmetric L-shape) and the resulting "convex pieces" silently aren't a
+/// decomposition of the original shape at all.
+fn convex_pieces(ring: &[(f64, f64)]) -> Vec<Vec<(f64, f64)>> {
+    if is_convex(ring) {
+        return vec![ring.to_vec()];
+    }
+    ear_clip(ring)
+}
+
+/// True when `p` lies inside the triangle `(a, b, c)` or on its boundary.
+fn point_in_triangle(p: (f64, f64), a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> bool {
+    let sign = |p1: (f64, f64), p2: (f64, f64), p3: (f64, f64)| {
+        (p1.0 - p3.0) * (p2.1 - p3.1) - (p2.0 - p3.0) * (p1.1 - p3.1)
+    };
+    let d1 = sign(p, a, b);
+    let d2 = sign(p, b, c);
+    let d3 = sign(p, c, a);
+    let has_neg = d1 < 0.0 || d2 < 0.0 || d3 < 0.0;
+    let has_pos = d1 > 0.0 || d2 > 0.0 || d3 > 0.0;
+    !(has_neg && has_pos)
+}
+
+/// Triangulate a simple polygon (convex or concave) by ear clipping: repeatedly
+/// cut off a "convex, empty" vertex (one whose interior angle is < 180° and
+/// whose triangle contains none of the ring's other vertices) until only a
+/// triangle remains. Unlike a centroid fan this always yields triangles that
+/// are genuine subsets of the input polygon.
+fn ear_clip(ring: &[(f64, f64)]) -> Vec<Vec<(f64, f64)>> {
+    let mut verts = ensure_ccw(ring);
+    let mut triangles = Vec::new();
+
+    while verts.len() > 3 {
+        let n = verts.len();
+        let mut clipped = false;
+        for i in 0..n {
+            let prev = verts[(i + n - 1) % n];
+            let cur = verts[i];
+            let next = verts[(i + 1) % n];
+
+            let cross = (cur.0 - prev.0) * (next.1 - cur.1) - (cur.1 - prev.1) * (next.0 - cur.0);
+            if cross <= 1e-9 {
+                continue; // reflex or degenerate vertex, not an ear
+            }
+
+            let is_empty = verts
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != (i + n - 1) % n && j != i && j != (i + 1) % n)
+                .all(|(_, &v)| !point_in_triangle(v, prev, cur, next));
+            if !is_empty {
+                continue;
+            }
+
+            triangles.push(vec![prev, cur, next]);
+            verts.remove(i);
+            clipped = true;
+            break;
+        }
+
+        if !clipped {
+            // Degenerate input (e.g. collinear/self-intersecting ring): give up
+            // clipping further and keep the remaining ring as one final piece,
+            // as-is, rather than looping forever.
+            break;
+        }
+    }
+    triangles.push(verts);
+    triangles
+}
+
+/// The inner-fit polygon: the sheet rectangle shrunk by the carpet's
+/// extent, i.e. every position the carpet's min-corner reference point can
+/// take while the carpet itself stays within the sheet.
+fn inner_fit_polygon(carpet_bounds: &Rectangle, sheet_width: f64, sheet_height: f64) -> Rectangle {
+    let width = carpet_bounds.max_x - carpet_bounds.min_x;
+    let height = carpet_bounds.max_y - carpet_bounds.min_y;
+    Rectangle::new(0.0, 0.0, (sheet_width - width).max(0.0), (sheet_height - height).max(0.0))
+}
+
+fn rectangle_contains_point(rect: &Rectangle, point: (f64, f64)) -> bool {
+    point.0 >= rect.min_x && point.0 <= rect.max_x &&
+    point.1 >= rect.min_y && point.1 <= rect.max_y
+}
+
+/// No-fit-polygon based placement: returns touching-but-not-overlapping
+/// candidate positions for `carpet_ring` (given relative to its own
+/// min-corner, i.e. `bounds_of(carpet_ring).min_{x,y} == 0`) against the
+/// already-placed `placed_rings`, picking the bottom-left-most candidate
+/// that also clears every other placed piece.
+#[pyfunction]
+fn nfp_place(
+    carpet_ring: Vec<(f64, f64)>,
+    placed_rings: Vec<Vec<(f64, f64)>>,
+    sheet_width: f64,
+    sheet_height: f64,
+) -> Option<(f64, f64)> {
+    let carpet_bounds = bounds_of(&carpet_ring);
+    let carpet_width = carpet_bounds.max_x - carpet_bounds.min_x;
+    let carpet_height = carpet_bounds.max_y - carpet_bounds.min_y;
+    if carpet_width > sheet_width || carpet_height > sheet_height {
+        // Too big for the sheet regardless of obstacles; `inner_fit_polygon`
+        // would otherwise clamp the oversized extent to zero instead of
+        // reporting that no placement exists.
+        return None;
+    }
+
+    let inner_fit = inner_fit_polygon(&carpet_bounds, sheet_width, sheet_height);
+
+    if placed_rings.is_empty() {
+        // Nothing to touch yet: bottom-left corner of the inner-fit polygon
+        return Some((inner_fit.min_x, inner_fit.min_y));
+    }
+
+    // One entry per obstacle, each holding that obstacle's (un-unioned)
+    // sub-NFP rings
+    let nfps: Vec<Vec<Vec<(f64, f64)>>> = placed_rings
+        .iter()
+        .map(|obstacle| no_fit_polygon(&carpet_ring, obstacle))
+        .collect();
+
+    let mut candidates: Vec<(f64, f64)> = nfps
+        .iter()
+        .flatten()
+        .flatten()
+        .copied()
+        .filter(|p| rectangle_contains_point(&inner_fit, *p))
+        .collect();
+    candidates.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap().then(a.0.partial_cmp(&b.0).unwrap()));
+
+    // A candidate is valid only if `point_in_polygon` places it inside none
+    // of the sub-NFP rings of any obstacle, its own included. The ray-casting
+    // test counts some boundary points as inside, so some touching
+    // candidates are rejected too.
+    candidates
+        .into_iter()
+        .find(|&point| nfps.iter().all(|obstacle_nfps| !inside_any_nfp(point, obstacle_nfps)))
+}
+
+#[cfg(test)]
+mod nfp_tests {
+    use super::*;
+
+    #[test]
+    fn nfp_place_rejects_carpet_wider_than_the_sheet() {
+        let carpet_ring = vec![(0.0, 0.0), (10.0, 0.0), (10.0, 2.0), (0.0, 2.0)];
+        assert_eq!(nfp_place(carpet_ring, vec![], 5.0, 5.0), None);
+    }
+
+    #[test]
+    fn nfp_place_keeps_concave_carpet_clear_of_distant_obstacle() {
+        // L-shaped (concave) carpet, relative to its own min corner
+        let carpet_ring = vec![
+            (0.0, 0.0), (4.0, 0.0), (4.0, 2.0),
+            (2.0, 2.0), (2.0, 4.0), (0.0, 4.0),
+        ];
+        // A small square obstacle well away from the origin
+        let obstacle = vec![(10.0, 10.0), (12.0, 10.0), (12.0, 12.0), (10.0, 12.0)];
+
+        let (x, y) = nfp_place(carpet_ring.clone(), vec![obstacle.clone()], 20.0, 20.0)
+            .expect("a free placement should exist on a mostly-empty sheet");
+
+        // An NFP candidate sits on the boundary of the no-fit region by
+        // construction, so it may legitimately touch the obstacle at a single
+        // vertex or edge; what must not happen is a positive-area overlap.
+        let placed_ring: Vec<(f64, f64)> = carpet_ring.iter().map(|(cx, cy)| (cx + x, cy + y)).collect();
+        let intersection = to_geo_polygon(&placed_ring).intersection(&to_geo_polygon(&obstacle));
+        assert!(intersection.unsigned_area() < 1e-6);
+    }
+}
+
+/// Swept-AABB collision time of `rect` moving by `(vx, vy)` against a
+/// static `other`, using the slab method. Returns the fraction of the
+/// movement (in `[0, 1]`) at which the two rectangles first touch, or
+/// `None` if they never touch along this movement.
+fn swept_collision_time(rect: &Rectangle, other: &Rectangle, vx: f64, vy: f64) -> Option<f64> {
+    let axis_times = |min: f64, max: f64, other_min: f64, other_max: f64, v: f64| -> Option<(f64, f64)> {
+        if v > 0.0 {
+            Some(((other_min - max) / v, (other_max - min) / v))
+        } else if v < 0.0 {
+            Some(((other_max - min) / v, (other_min - max) / v))
+        } else if max <= other_min || other_max <= min {
+            // No movement on this axis and already separated on it: never collides
+            None
+        } else {
+            // No movement but already overlapping on this axis: this axis never blocks
+            Some((f64::NEG_INFINITY, f64::INFINITY))
+        }
+    };
+
+    let (entry_x, exit_x) = axis_times(rect.min_x, rect.max_x, other.min_x, other.max_x, vx)?;
+    let (entry_y, exit_y) = axis_times(rect.min_y, rect.max_y, other.min_y, other.max_y, vy)?;
+
+    let entry = entry_x.max(entry_y);
+    let exit = exit_x.min(exit_y);
+
+    if entry > exit || !(0.0..=1.0).contains(&entry) {
+        None
+    } else {
+        Some(entry)
+    }
+}
+
+/// Slide `rect` from its current position along `(dir_x, dir_y)` until it
+/// first contacts an obstacle or a sheet wall, returning the stop position.
+/// `swept_collision_time` treats its velocity as the full displacement
+/// over `t in [0, 1]`, but callers only supply a direction, not how far to
+/// travel — so the direction is rescaled here to a length that's
+/// guaranteed to cross the whole sheet before the slab test runs.
+fn swept_slide(
+    rect: &Rectangle,
+    obstacles: &[Rectangle],
+    dir_x: f64,
+    dir_y: f64,
+    sheet_width: f64,
+    sheet_height: f64,
+) -> (f64, f64) {
+    let magnitude = (dir_x * dir_x + dir_y * dir_y).sqrt();
+    if magnitude == 0.0 {
+        return (rect.min_x, rect.min_y);
+    }
+    let reach = sheet_width + sheet_height;
+    let vx = dir_x / magnitude * reach;
+    let vy = dir_y / magnitude * reach;
+
+    // Treat the sheet boundary as four static walls, one in the direction of travel
+    let mut wall_time: f64 = 1.0;
+    if vx > 0.0 {
+        wall_time = wall_time.min((sheet_width - rect.max_x) / vx);
+    } else if vx < 0.0 {
+        wall_time = wall_time.min((0.0 - rect.min_x) / vx);
+    }
+    if vy > 0.0 {
+        wall_time = wall_time.min((sheet_height - rect.max_y) / vy);
+    } else if vy < 0.0 {
+        wall_time = wall_time.min((0.0 - rect.min_y) / vy);
+    }
+
+    let min_time = obstacles
+        .iter()
+        .filter_map(|obstacle| swept_collision_time(rect, obstacle, vx, vy))
+        .fold(wall_time.max(0.0), f64::min);
+
+    (rect.min_x + vx * min_time, rect.min_y + vy * min_time)
+}
+
+/// "Drop and slide" placement: slides a carpet from `start` along `direction`
+/// until it first contacts an obstacle or sheet wall, then optionally
+/// repeats along a second direction to settle-then-shift, mimicking
+/// gravity-style bottom-left packing without depending on `grid_size`.
+#[pyfunction]
+fn swept_place(
+    carpet_bounds: (f64, f64, f64, f64),
+    start: (f64, f64),
+    placed_bounds: Vec<(f64, f64, f64, f64)>,
+    sheet_width: f64,
+    sheet_height: f64,
+    direction: (f64, f64),
+    settle_direction: Option<(f64, f64)>,
+) -> (f64, f64) {
+    let (min_x, min_y, max_x, max_y) = carpet_bounds;
+    let start_rect = Rectangle::new(
+        start.0,
+        start.1,
+        start.0 + (max_x - min_x),
+        start.1 + (max_y - min_y),
+    );
+
+    let obstacles: Vec<Rectangle> = placed_bounds
+        .into_iter()
+        .map(|(min_x, min_y, max_x, max_y)| Rectangle::new(min_x, min_y, max_x, max_y))
+        .collect();
+
+    let (x, y) = swept_slide(&start_rect, &obstacles, direction.0, direction.1, sheet_width, sheet_height);
+
+    if let Some((sx, sy)) = settle_direction {
+        let settled_rect = Rectangle::new(x, y, x + (max_x - min_x), y + (max_y - min_y));
+        swept_slide(&settled_rect, &obstacles, sx, sy, sheet_width, sheet_height)
+    } else {
+        (x, y)
+    }
+}
+
+#[cfg(test)]
+mod swept_tests {
+    use super::*;
+
+    #[test]
+    fn swept_place_stops_at_sheet_wall_with_no_obstacles() {
+        let (x, y) = swept_place((0.0, 0.0, 2.0, 2.0), (0.0, 0.0), vec![], 10.0, 10.0, (1.0, 0.0), None);
+        assert_eq!((x, y), (8.0, 0.0));
+    }
+
+    #[test]
+    fn swept_place_stops_at_obstacle_contact() {
+        let obstacle = (5.0, 0.0, 7.0, 2.0);
+        let (x, y) = swept_place((0.0, 0.0, 2.0, 2.0), (0.0, 0.0), vec![obstacle], 10.0, 10.0, (1.0, 0.0), None);
+        assert_eq!((x, y), (3.0, 0.0));
+    }
+}
+
+/// Rotate a vertex ring by `angle_degrees` about its own centroid
+fn rotate_ring(ring: &[(f64, f64)], angle_degrees: f64) -> Vec<(f64, f64)> {
+    let n = ring.len() as f64;
+    let cx = ring.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let cy = ring.iter().map(|(_, y)| y).sum::<f64>() / n;
+
+    let theta = angle_degrees.to_radians();
+    let (sin, cos) = theta.sin_cos();
+
+    ring.iter()
+        .map(|(x, y)| {
+            let (dx, dy) = (x - cx, y - cy);
+            (cx + dx * cos - dy * sin, cy + dx * sin + dy * cos)
+        })
+        .collect()
+}
+
+/// One (angle, rotated ring, rotated ring's AABB, x, y) grid candidate for
+/// `fast_grid_search_rotated`
+type RotatedCandidate = (f64, Vec<(f64, f64)>, Rectangle, f64, f64);
+
+/// Grid search for collision-free positions that also exploits rotation
+/// freedom: each candidate position is tried at every angle in
+/// `allowed_angles`, flat-mapping over the (position x angle) product so
+/// rayon still parallelizes across the whole candidate set. Returns the
+/// first collision-free `(x, y, angle)` found, where `x, y` place the
+/// rotated carpet's bounding-box min corner.
+#[pyfunction]
+fn fast_grid_search_rotated(
+    carpet_ring: Vec<(f64, f64)>,  // carpet vertex ring at its reference position
+    placed_rings: Vec<Vec<(f64, f64)>>,  // vertex rings of already-placed polygons
+    sheet_width: f64,
+    sheet_height: f64,
+    grid_size: usize,
+    allowed_angles: Vec<f64>,
+) -> Option<(f64, f64, f64)> {
+    let obstacle_bounds: Vec<Rectangle> = placed_rings.iter().map(|r| bounds_of(r)).collect();
+
+    // Every (angle, grid position) candidate, flat-mapped up front so rayon
+    // parallelizes over the whole position x angle product
+    let candidates: Vec<RotatedCandidate> = allowed_angles
+        .iter()
+        .flat_map(|&angle| {
+            let ring = rotate_ring(&carpet_ring, angle);
+            let ring_bounds = bounds_of(&ring);
+            let width = ring_bounds.max_x - ring_bounds.min_x;
+            let height = ring_bounds.max_y - ring_bounds.min_y;
+
+            let x_step = if grid_size > 1 { (sheet_width - width) / (grid_size as f64 - 1.0) } else { 0.0 };
+            let y_step = if grid_size > 1 { (sheet_height - height) / (grid_size as f64 - 1.0) } else { 0.0 };
+
+            (0..grid_size).flat_map(move |i| {
+                let ring = ring.clone();
+                let ring_bounds = ring_bounds.clone();
+                (0..grid_size).map(move |j| {
+                    let x = if grid_size == 1 { 0.0 } else { i as f64 * x_step };
+                    let y = if grid_size == 1 { 0.0 } else { j as f64 * y_step };
+                    (angle, ring.clone(), ring_bounds.clone(), x, y)
+                })
+            })
+        })
+        .collect();
+
+    candidates
+        .par_iter()
+        .find_first(|(_, ring, ring_bounds, x, y)| {
+            let dx = x - ring_bounds.min_x;
+            let dy = y - ring_bounds.min_y;
+            let test_bounds = ring_bounds.translate(dx, dy);
+
+            if test_bounds.min_x < 0.0 || test_bounds.min_y < 0.0 ||
+               test_bounds.max_x > sheet_width || test_bounds.max_y > sheet_height {
+                return false;
+            }
+
+            // Cheap AABB reject first, exact polygon test only for survivors
+            let hits: Vec<usize> = obstacle_bounds
+                .iter()
+                .enumerate()
+                .filter(|(_, bounds)| test_bounds.intersects(bounds))
+                .map(|(idx, _)| idx)
+                .collect();
+
+            if hits.is_empty() {
+                return true;
+            }
+
+            let test_ring: Vec<(f64, f64)> = ring.iter().map(|(x, y)| (x + dx, y + dy)).collect();
+
+            !hits.iter().any(|&idx| polygons_overlap(&test_ring, &placed_rings[idx]))
+        })
+        .map(|(angle, _, _, x, y)| (*x, *y, *angle))
+}
+
+/// One (angle, rotated ring, rotated ring's AABB) entry for
+/// `batch_collision_check_rotated`
+type RotatedRing = (f64, Vec<(f64, f64)>, Rectangle);
+
+/// Rotation-aware collision check: for each `(x, y)` position, tests the
+/// carpet at every angle in `allowed_angles` and reports the first angle
+/// that's collision-free, or `None` if no rotation clears the obstacles.
+#[pyfunction]
+fn batch_collision_check_rotated(
+    carpet_ring: Vec<(f64, f64)>,
+    positions: Vec<(f64, f64)>,
+    placed_rings: Vec<Vec<(f64, f64)>>,
+    sheet_width: f64,
+    sheet_height: f64,
+    allowed_angles: Vec<f64>,
+) -> Vec<Option<f64>> {
+    let obstacle_bounds: Vec<Rectangle> = placed_rings.iter().map(|r| bounds_of(r)).collect();
+
+    let rotated_rings: Vec<RotatedRing> = allowed_angles
+        .iter()
+        .map(|&angle| {
+            let ring = rotate_ring(&carpet_ring, angle);
+            let ring_bounds = bounds_of(&ring);
+            (angle, ring, ring_bounds)
+        })
+        .collect();
+
+    positions
+        .par_iter()
+        .map(|(x, y)| {
+            rotated_rings.iter().find_map(|(angle, ring, ring_bounds)| {
+                let dx = x - ring_bounds.min_x;
+                let dy = y - ring_bounds.min_y;
+                let test_bounds = ring_bounds.translate(dx, dy);
+
+                if test_bounds.min_x < 0.0 || test_bounds.min_y < 0.0 ||
+                   test_bounds.max_x > sheet_width || test_bounds.max_y > sheet_height {
+                    return None;
+                }
+
+                let hits: Vec<usize> = obstacle_bounds
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, bounds)| test_bounds.intersects(bounds))
+                    .map(|(idx, _)| idx)
+                    .collect();
+
+                let test_ring: Vec<(f64, f64)> = ring.iter().map(|(x, y)| (x + dx, y + dy)).collect();
+                let collides = hits.iter().any(|&idx| polygons_overlap(&test_ring, &placed_rings[idx]));
+
+                if collides { None } else { Some(*angle) }
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod rotated_search_tests {
+    use super::*;
+
+    // A 1x4 carpet and an obstacle spanning y in [1, 4] across the whole 4x4
+    // sheet: the only gap is a 4-wide, 1-tall strip at the bottom, which the
+    // carpet can only occupy once rotated 90 degrees onto its side.
+    fn carpet_and_blocking_obstacle() -> (Vec<(f64, f64)>, Vec<(f64, f64)>) {
+        let carpet_ring = vec![(0.0, 0.0), (1.0, 0.0), (1.0, 4.0), (0.0, 4.0)];
+        let obstacle = vec![(0.0, 1.0), (4.0, 1.0), (4.0, 4.0), (0.0, 4.0)];
+        (carpet_ring, obstacle)
+    }
+
+    #[test]
+    fn fast_grid_search_rotated_finds_placement_unrotated_search_misses() {
+        let (carpet_ring, obstacle) = carpet_and_blocking_obstacle();
+
+        assert!(
+            fast_grid_search_rotated(carpet_ring.clone(), vec![obstacle.clone()], 4.0, 4.0, 3, vec![0.0])
+                .is_none(),
+            "an unrotated 1x4 carpet can never clear a 4x3 obstacle on a 4x4 sheet"
+        );
+
+        let (x, y, angle) =
+            fast_grid_search_rotated(carpet_ring, vec![obstacle], 4.0, 4.0, 3, vec![0.0, 90.0])
+                .expect("rotating 90 degrees should uncover the bottom strip");
+        assert_eq!((x, y, angle), (0.0, 0.0, 90.0));
+    }
+
+    #[test]
+    fn batch_collision_check_rotated_reports_the_clearing_angle() {
+        let (carpet_ring, obstacle) = carpet_and_blocking_obstacle();
+
+        let results = batch_collision_check_rotated(
+            carpet_ring,
+            vec![(0.0, 0.0)],
+            vec![obstacle],
+            4.0,
+            4.0,
+            vec![0.0, 90.0],
+        );
+        assert_eq!(results, vec![Some(90.0)]);
+    }
+}
+
 /// Fast grid search for collision-free positions
 #[pyfunction]
 fn fast_grid_search(
@@ -148,23 +960,54 @@ fn batch_collision_check(
         .collect()
 }
 
-/// Spatial index for very fast collision queries
+/// A rectangle tagged with a stable id, so callers can correlate query
+/// results back to their own piece list across inserts and removes.
+#[derive(Debug, Clone, PartialEq)]
+struct TaggedRectangle {
+    id: usize,
+    rect: Rectangle,
+}
+
+impl rstar::RTreeObject for TaggedRectangle {
+    type Envelope = AABB<[f64; 2]>;
+
+    fn envelope(&self) -> Self::Envelope {
+        self.rect.envelope()
+    }
+}
+
+impl rstar::PointDistance for TaggedRectangle {
+    fn distance_2(&self, point: &[f64; 2]) -> f64 {
+        self.rect.distance_2(point)
+    }
+}
+
+/// Spatial index for very fast collision queries. Backed by an `RTree` that
+/// supports incremental `insert`/`remove`, so an iterative placement loop
+/// doesn't have to rebuild the whole tree after every piece.
 #[pyclass]
 struct SpatialIndex {
-    rtree: RTree<Rectangle>,
+    rtree: RTree<TaggedRectangle>,
+    next_id: usize,
 }
 
 #[pymethods]
 impl SpatialIndex {
     #[new]
     fn new(bounds_list: Vec<(f64, f64, f64, f64)>) -> Self {
-        let rectangles: Vec<Rectangle> = bounds_list
+        let rectangles: Vec<TaggedRectangle> = bounds_list
             .into_iter()
-            .map(|(min_x, min_y, max_x, max_y)| Rectangle::new(min_x, min_y, max_x, max_y))
+            .enumerate()
+            .map(|(id, (min_x, min_y, max_x, max_y))| TaggedRectangle {
+                id,
+                rect: Rectangle::new(min_x, min_y, max_x, max_y),
+            })
             .collect();
+        let next_id = rectangles.len();
 
         SpatialIndex {
             rtree: RTree::bulk_load(rectangles),
+            next_id,
         }
     }
 
@@ -179,9 +1022,100 @@ impl SpatialIndex {
             [test_rect.max_x, test_rect.max_y]
         )).next().is_some()
     }
+
+    /// Indices of every rectangle intersecting `test_bounds`, not just a bool
+    fn query_all_collisions(&self, test_bounds: (f64, f64, f64, f64)) -> Vec<usize> {
+        let test_rect = Rectangle::new(
+            test_bounds.0, test_bounds.1,
+            test_bounds.2, test_bounds.3
+        );
+
+        self.rtree
+            .locate_in_envelope_intersecting(&AABB::from_corners(
+                [test_rect.min_x, test_rect.min_y],
+                [test_rect.max_x, test_rect.max_y],
+            ))
+            .map(|tagged| tagged.id)
+            .collect()
+    }
+
+    /// Insert a new rectangle and return the stable id it was tagged with
+    fn insert(&mut self, bounds: (f64, f64, f64, f64)) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        self.rtree.insert(TaggedRectangle {
+            id,
+            rect: Rectangle::new(bounds.0, bounds.1, bounds.2, bounds.3),
+        });
+        id
+    }
+
+    /// Remove the rectangle with the given id, returning whether it was found
+    fn remove(&mut self, id: usize) -> bool {
+        let target = self.rtree.iter().find(|tagged| tagged.id == id).cloned();
+        match target {
+            Some(tagged) => self.rtree.remove(&tagged).is_some(),
+            None => false,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.rtree.size()
+    }
+
+    /// Bounds and id of the placed rectangle closest to `point`, using the
+    /// already-implemented `PointDistance::distance_2`
+    fn nearest_free_gap(&self, point: (f64, f64)) -> Option<(usize, f64, f64, f64, f64)> {
+        self.rtree
+            .nearest_neighbor(&[point.0, point.1])
+            .map(|tagged| (tagged.id, tagged.rect.min_x, tagged.rect.min_y, tagged.rect.max_x, tagged.rect.max_y))
+    }
+}
+
+#[cfg(test)]
+mod spatial_index_tests {
+    use super::*;
+
+    #[test]
+    fn remove_drops_the_tagged_rectangle_not_a_stale_clone() {
+        let mut index = SpatialIndex::new(vec![(0.0, 0.0, 1.0, 1.0), (5.0, 5.0, 6.0, 6.0)]);
+        let id = index.insert((2.0, 2.0, 3.0, 3.0));
+
+        assert!(index.remove(id));
+        assert_eq!(index.len(), 2);
+        assert!(index.query_all_collisions((2.0, 2.0, 3.0, 3.0)).is_empty());
+
+        // Removing the same id again must fail: it's no longer in the tree
+        assert!(!index.remove(id));
+    }
+
+    #[test]
+    fn query_all_collisions_returns_every_overlapping_id() {
+        let index = SpatialIndex::new(vec![
+            (0.0, 0.0, 2.0, 2.0),
+            (1.0, 1.0, 3.0, 3.0),
+            (10.0, 10.0, 11.0, 11.0),
+        ]);
+
+        let mut hits = index.query_all_collisions((0.5, 0.5, 1.5, 1.5));
+        hits.sort();
+        assert_eq!(hits, vec![0, 1]);
+    }
+
+    #[test]
+    fn nearest_free_gap_returns_the_closer_of_two_candidates() {
+        let index = SpatialIndex::new(vec![(0.0, 0.0, 1.0, 1.0), (100.0, 100.0, 101.0, 101.0)]);
+
+        let (id, min_x, min_y, max_x, max_y) = index
+            .nearest_free_gap((0.5, 0.5))
+            .expect("the index is non-empty, so a nearest neighbor must exist");
+
+        assert_eq!(id, 0);
+        assert_eq!((min_x, min_y, max_x, max_y), (0.0, 0.0, 1.0, 1.0));
+    }
 }
 
-// Implement RTreeObject for Rectangle
 impl rstar::RTreeObject for Rectangle {
     type Envelope = AABB<[f64; 2]>;
 
@@ -198,11 +1132,165 @@ impl rstar::PointDistance for Rectangle {
     }
 }
 
+/// A reasonable default cell size for `HashGridIndex`: the mean of each
+/// piece's own (width + height) / 2, so buckets are sized to the pieces
+/// actually being nested instead of a fixed constant.
+#[pyfunction]
+fn suggested_cell_size(bounds_list: Vec<(f64, f64, f64, f64)>) -> f64 {
+    if bounds_list.is_empty() {
+        return 1.0;
+    }
+    let total: f64 = bounds_list
+        .iter()
+        .map(|(min_x, min_y, max_x, max_y)| ((max_x - min_x) + (max_y - min_y)) / 2.0)
+        .sum();
+    total / bounds_list.len() as f64
+}
+
+/// Uniform spatial-hash broadphase: an alternative to `SpatialIndex` for
+/// workloads with thousands of similarly sized carpets on one sheet, where
+/// a flat grid's query cost beats an R-tree's.
+#[pyclass]
+struct HashGridIndex {
+    cell_size: f64,
+    cells: HashMap<(i32, i32), Vec<usize>>,
+    rectangles: Vec<Rectangle>,
+}
+
+impl HashGridIndex {
+    fn cell_range(&self, rect: &Rectangle) -> ((i32, i32), (i32, i32)) {
+        let min_cell = (
+            (rect.min_x / self.cell_size).floor() as i32,
+            (rect.min_y / self.cell_size).floor() as i32,
+        );
+        let max_cell = (
+            (rect.max_x / self.cell_size).floor() as i32,
+            (rect.max_y / self.cell_size).floor() as i32,
+        );
+        (min_cell, max_cell)
+    }
+}
+
+#[pymethods]
+impl HashGridIndex {
+    #[new]
+    fn new(cell_size: f64, bounds_list: Vec<(f64, f64, f64, f64)>) -> Self {
+        let rectangles: Vec<Rectangle> = bounds_list
+            .into_iter()
+            .map(|(min_x, min_y, max_x, max_y)| Rectangle::new(min_x, min_y, max_x, max_y))
+            .collect();
+
+        let mut index = HashGridIndex {
+            cell_size,
+            cells: HashMap::new(),
+            rectangles: Vec::new(),
+        };
+        for rect in rectangles {
+            index.insert((rect.min_x, rect.min_y, rect.max_x, rect.max_y));
+        }
+        index
+    }
+
+    /// Stamp a rectangle's id into every cell its AABB overlaps
+    fn insert(&mut self, bounds: (f64, f64, f64, f64)) -> usize {
+        let id = self.rectangles.len();
+        let rect = Rectangle::new(bounds.0, bounds.1, bounds.2, bounds.3);
+
+        let (min_cell, max_cell) = self.cell_range(&rect);
+        for cx in min_cell.0..=max_cell.0 {
+            for cy in min_cell.1..=max_cell.1 {
+                self.cells.entry((cx, cy)).or_default().push(id);
+            }
+        }
+        self.rectangles.push(rect);
+        id
+    }
+
+    /// Visit only the cells `test_bounds` covers, running the exact
+    /// rectangle test on the small candidate set and deduplicating
+    /// across cells
+    fn query_collisions(&self, test_bounds: (f64, f64, f64, f64)) -> bool {
+        !self.candidates(test_bounds).is_empty()
+    }
+
+    fn batch_collision_check(&self, positions: Vec<(f64, f64, f64, f64)>) -> Vec<bool> {
+        positions
+            .par_iter()
+            .map(|&bounds| !self.candidates(bounds).is_empty())
+            .collect()
+    }
+
+    fn len(&self) -> usize {
+        self.rectangles.len()
+    }
+}
+
+#[cfg(test)]
+mod hash_grid_index_tests {
+    use super::*;
+
+    #[test]
+    fn query_collisions_finds_overlap_across_cell_boundaries() {
+        let mut index = HashGridIndex::new(2.0, vec![(0.0, 0.0, 1.0, 1.0)]);
+        let id = index.insert((3.9, 0.0, 4.9, 1.0));
+
+        assert_eq!(index.len(), 2);
+        assert_eq!(id, 1);
+        // The second rect straddles the cell boundary at x=4.0, so it is
+        // stamped into cells 1 and 2; this query lies wholly in cell 2
+        assert!(index.query_collisions((4.5, 0.0, 5.5, 1.0)));
+        assert!(!index.query_collisions((10.0, 10.0, 11.0, 11.0)));
+    }
+
+    #[test]
+    fn batch_collision_check_reports_one_result_per_position() {
+        let index = HashGridIndex::new(2.0, vec![(0.0, 0.0, 1.0, 1.0)]);
+
+        let results = index.batch_collision_check(vec![
+            (0.5, 0.5, 1.5, 1.5),     // overlaps the inserted rectangle
+            (10.0, 10.0, 11.0, 11.0), // far away, no overlap
+        ]);
+        assert_eq!(results, vec![true, false]);
+    }
+}
+
+impl HashGridIndex {
+    /// Ids of every inserted rectangle that truly intersects `test_bounds`,
+    /// deduplicated across the cells the query rectangle covers
+    fn candidates(&self, test_bounds: (f64, f64, f64, f64)) -> Vec<usize> {
+        let test_rect = Rectangle::new(test_bounds.0, test_bounds.1, test_bounds.2, test_bounds.3);
+        let (min_cell, max_cell) = self.cell_range(&test_rect);
+
+        let mut seen = std::collections::HashSet::new();
+        let mut hits = Vec::new();
+        for cx in min_cell.0..=max_cell.0 {
+            for cy in min_cell.1..=max_cell.1 {
+                if let Some(ids) = self.cells.get(&(cx, cy)) {
+                    for &id in ids {
+                        if seen.insert(id) && self.rectangles[id].intersects(&test_rect) {
+                            hits.push(id);
+                        }
+                    }
+                }
+            }
+        }
+        hits
+    }
+}
+
 /// Python module
 #[pymodule]
 fn layout_optimizer_rust(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(fast_grid_search, m)?)?;
+    m.add_function(wrap_pyfunction!(fast_grid_search_polygon, m)?)?;
     m.add_function(wrap_pyfunction!(batch_collision_check, m)?)?;
+    m.add_function(wrap_pyfunction!(polygon_collision_check, m)?)?;
+    m.add_function(wrap_pyfunction!(nfp_place, m)?)?;
+    m.add_function(wrap_pyfunction!(swept_place, m)?)?;
+    m.add_function(wrap_pyfunction!(suggested_cell_size, m)?)?;
+    m.add_function(wrap_pyfunction!(fast_grid_search_rotated, m)?)?;
+    m.add_function(wrap_pyfunction!(batch_collision_check_rotated, m)?)?;
     m.add_class::<SpatialIndex>()?;
+    m.add_class::<HashGridIndex>()?;
     Ok(())
 }
\ No newline at end of file